@@ -5,9 +5,10 @@
 //! - Time complexity is `O(1)` per push amortized over every possible input sequence, and 
 //!   `O(log n)` worst case (if the inputs are already sorted)
 //! - No heap allocations except when creating a new `Shortlist`
-//! - 0 dependencies, and only ~150 lines of source code
-//! - 'Safe' versions are provided for functions that contain `unsafe` code in order to prevent
-//!   heap allocations
+//! - 0 required dependencies (optional integrations, like weighted sampling via `rand` or
+//!   (de)serialization via `serde`, are feature-gated)
+//! - Items can be ordered by their own `Ord` implementation, or by a custom comparator/key
+//!   function (see [`Shortlist::new_by`] and [`Shortlist::new_by_key`])
 //!
 //! # The Problem
 //! Suppose that you are running a brute force search over a very large search space, but want to
@@ -105,8 +106,8 @@
 
 #![deny(clippy::cargo)]
 
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+use std::fmt;
 
 /// A datastructure that tracks the largest items pushed to it with no heap allocations and `O(1)`
 /// amortized time per push.
@@ -116,6 +117,13 @@ use std::collections::BinaryHeap;
 /// one heap allocation when the `Shortlist` is created and every subsequent operation will be
 /// allocation free.
 ///
+/// By default, items are ordered by their own [`Ord`] implementation, but [`Shortlist::new_by`]
+/// and [`Shortlist::new_by_key`] can be used to shortlist items that aren't `Ord` (e.g. items
+/// scored by an `f64`), or to order by a projection of a larger payload. The comparator is stored
+/// as the second type parameter `F`, monomorphized per-comparator rather than boxed, so a
+/// `Shortlist` ordered by a custom comparator places no extra requirements (like `'static`) on `T`
+/// and stays `Send`/`Sync` whenever `T` and the comparator are.
+///
 /// # Example
 /// Find the top `100` values from 1000 randomly generated integers without storing more than 100
 /// integers on the heap at a time.
@@ -133,13 +141,29 @@ use std::collections::BinaryHeap;
 /// // Consume the shortlist and print its top 100 items in ascending order
 /// println!("{:?}", shortlist.into_sorted_vec());
 /// ```
-#[derive(Debug, Clone)]
-pub struct Shortlist<T> {
-    heap: BinaryHeap<Reverse<T>>,
+#[derive(Clone)]
+pub struct Shortlist<T, F = fn(&T, &T) -> Ordering> {
+    /// A min-max heap over `T`, ordered by `compare`.  The item at index `0` (if any) is always
+    /// the current minimum, i.e. the eviction threshold; the current maximum is one of the items
+    /// at index `1` or `2`.  See [`Shortlist::threshold`] and [`Shortlist::peek_max`].
+    heap: Vec<T>,
+    capacity: usize,
+    /// The comparator used to order `heap`; defaults to `T::cmp` (see [`Shortlist::new`]).
+    compare: F,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Shortlist<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shortlist")
+            .field("heap", &self.heap)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
 }
 
 impl<T: Ord> Shortlist<T> {
-    /// Creates a new empty `Shortlist` with a given capacity.
+    /// Creates a new empty `Shortlist` with a given capacity, ordering items by their own
+    /// [`Ord`] implementation.
     ///
     /// The capacity is the maximum number of items that the `Shortlist` will store at an any one
     /// time.
@@ -158,10 +182,7 @@ impl<T: Ord> Shortlist<T> {
     /// assert!(shortlist.is_empty());
     /// ```
     pub fn new(capacity: usize) -> Shortlist<T> {
-        assert!(capacity > 0, "Cannot create a Shortlist with capacity 0.");
-        Shortlist {
-            heap: BinaryHeap::with_capacity(capacity),
-        }
+        Shortlist::new_by(capacity, T::cmp as fn(&T, &T) -> Ordering)
     }
 
     /// Creates a new `Shortlist` with a given capacity that contains [`Clone`]s of the largest
@@ -211,13 +232,101 @@ impl<T: Ord> Shortlist<T> {
     /// let contents = [0, 3, 6, 5, 2, 1, 4, 6, 7];
     /// let shortlist = Shortlist::from_iter(4, contents.iter().copied());
     /// // The top 4 items of `contents` is [5, 6, 6, 7]
-    /// assert_eq!(shortlist.into_vec(), vec![5, 6, 6, 7]);
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![5, 6, 6, 7]);
     /// ```
+    ///
+    /// This is a distinct, inherent method rather than an implementation of the standard
+    /// [`FromIterator`] trait, since the latter has no way to thread a `capacity` through
+    /// `.collect()`. `Shortlist` does also implement `FromIterator`, for `.collect()`-ing into an
+    /// (unbounded) `Shortlist` whose capacity is just the number of items produced (or `1` if
+    /// there are none).
     pub fn from_iter(capacity: usize, contents: impl IntoIterator<Item = T>) -> Shortlist<T> {
         let mut shortlist = Shortlist::new(capacity);
         shortlist.append(contents);
         shortlist
     }
+}
+
+impl<T> Shortlist<T> {
+    /// Creates a new empty `Shortlist` with a given capacity, ordering items by a key extracted
+    /// from each item.
+    ///
+    /// This is the common case of [`Shortlist::new_by`], useful for keeping the top items from a
+    /// search ranked by a score that doesn't itself implement `Ord` (e.g. an `f64`), or by a
+    /// projection of a larger payload.
+    ///
+    /// # Panics
+    /// Creating a `Shortlist` with capacity `0` is a logical error and will cause a panic.
+    /// Additionally, `push`ing two items whose keys cannot be compared (e.g. `f64::NAN`) will
+    /// cause a panic.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// struct Candidate {
+    ///     name: &'static str,
+    ///     score: f64,
+    /// }
+    ///
+    /// // Keep the top 2 candidates by `score`, without requiring `Candidate: Ord`
+    /// let mut shortlist = Shortlist::new_by_key(2, |c: &Candidate| c.score);
+    /// shortlist.append([
+    ///     Candidate { name: "Alice", score: 0.4 },
+    ///     Candidate { name: "Bob", score: 0.9 },
+    ///     Candidate { name: "Carol", score: 0.7 },
+    /// ]);
+    /// let mut names: Vec<&str> = shortlist.iter().map(|c| c.name).collect();
+    /// names.sort();
+    /// assert_eq!(names, vec!["Bob", "Carol"]);
+    /// ```
+    pub fn new_by_key<K: PartialOrd>(
+        capacity: usize,
+        key: impl Fn(&T) -> K,
+    ) -> Shortlist<T, impl Fn(&T, &T) -> Ordering> {
+        Shortlist::new_by(capacity, move |a, b| {
+            key(a)
+                .partial_cmp(&key(b))
+                .expect("`new_by_key` comparator produced values that cannot be compared (e.g. NaN)")
+        })
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Shortlist<T, F> {
+    /// Creates a new empty `Shortlist` with a given capacity, ordering items using a custom
+    /// comparator rather than requiring `T: Ord`.
+    ///
+    /// This is the customization point that lets a `Shortlist` hold items that aren't `Ord` (for
+    /// example, items scored by an `f64`) by handing it a comparator, in the same way the std
+    /// [`BinaryHeap`](std::collections::BinaryHeap) docs demonstrate with a hand-written `Ord` for
+    /// Dijkstra states. If the ordering is really just a projection of a larger payload, consider
+    /// [`Shortlist::new_by_key`] instead.
+    ///
+    /// `compare` is stored directly as `self`'s second type parameter rather than being boxed, so
+    /// it need not be `'static`: a `Shortlist` can borrow from its environment for as long as the
+    /// comparator does.
+    ///
+    /// # Panics
+    /// Creating a `Shortlist` with capacity `0` is a logical error and will cause a panic.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    /// use std::cmp::Ordering;
+    ///
+    /// // Keep the 2 candidates with the lowest score (i.e. reverse the usual ordering)
+    /// let mut shortlist = Shortlist::new_by(2, |a: &i32, b: &i32| b.cmp(a));
+    /// shortlist.append([5, 1, 3, 9, 0]);
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![1, 0]);
+    /// ```
+    pub fn new_by(capacity: usize, compare: F) -> Shortlist<T, F> {
+        assert!(capacity > 0, "Cannot create a Shortlist with capacity 0.");
+        Shortlist {
+            heap: Vec::with_capacity(capacity),
+            capacity,
+            compare,
+        }
+    }
 
     /// Add an item to the `Shortlist`.
     ///
@@ -226,15 +335,14 @@ impl<T: Ord> Shortlist<T> {
     /// new item will cause an existing item in the `Shortlist` to be dropped.
     ///
     /// If the `item` is big enough and there are at least two minimum values, exactly which of
-    /// these minimum items will be dropped is an implementation detail of the underlying
-    /// [`BinaryHeap`] and cannot be relied upon.
+    /// these minimum items will be dropped is an implementation detail of the internal heap and
+    /// cannot be relied upon.
     ///
     /// # Time Complexity
-    /// The amortized cost of this operation, over all possible input sequence is `O(1)` (same as
-    /// [`BinaryHeap::push`]).
+    /// The amortized cost of this operation, over all possible input sequence is `O(1)`.
     /// This degrades the more sorted the input sequence is.
-    /// However, **unlike** [`BinaryHeap::push`] this will never reallocate, so the worst case cost of
-    /// any single `push` is `O(log n)` where `n` is the length of the `Shortlist`.
+    /// This will never reallocate, so the worst case cost of any single `push` is `O(log n)`
+    /// where `n` is the length of the `Shortlist`.
     ///
     /// # Example
     /// ```
@@ -254,26 +362,93 @@ impl<T: Ord> Shortlist<T> {
     /// // We now expect the shortlist to contain [0, 3, 4]
     /// assert_eq!(shortlist.into_sorted_vec(), vec![0, 3, 4]);
     /// ```
+    #[inline]
     pub fn push(&mut self, item: T) {
-        if self.heap.len() < self.heap.capacity() {
-            // If the heap hasn't reached capacity we should always add the new item
-            self.heap.push(Reverse(item));
+        self.push_replace(item);
+    }
+
+    /// Add an item to the `Shortlist`, reporting whichever item ends up not making the cut.
+    ///
+    /// This is identical to [`Shortlist::push`], except that it reports what happened to the
+    /// `Shortlist` as a result of the push:
+    /// - `Some(evicted)` if `item` was big enough to enter the `Shortlist`, displacing `evicted`
+    ///   (which was the previous minimum).
+    /// - `Some(item)` if `item` was not big enough to enter the `Shortlist`, so `item` is simply
+    ///   handed back to the caller.
+    /// - `None` if the `Shortlist` was not yet at capacity, so `item` was added for free without
+    ///   evicting anything.
+    ///
+    /// This is useful in a hot loop that needs to do something with whatever value falls out of
+    /// the `Shortlist` (freeing resources, logging it, feeding it into a secondary pass, etc.),
+    /// since it avoids a separate peek-then-push to work out what happened.
+    ///
+    /// # Time Complexity
+    /// Same as [`Shortlist::push`].
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// // Keep track of the 3 largest items so far.
+    /// let mut shortlist = Shortlist::new(3);
+    ///
+    /// // The first 3 values are added for free, since the shortlist isn't yet full
+    /// assert_eq!(shortlist.push_replace(0), None);
+    /// assert_eq!(shortlist.push_replace(3), None);
+    /// assert_eq!(shortlist.push_replace(1), None);
+    /// // This value is too small to make the cut, so it is simply handed back
+    /// assert_eq!(shortlist.push_replace(0), Some(0));
+    /// // This value is big enough, so it evicts the current minimum (0)
+    /// assert_eq!(shortlist.push_replace(4), Some(0));
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![1, 3, 4]);
+    /// ```
+    pub fn push_replace(&mut self, item: T) -> Option<T> {
+        if self.heap.len() < self.capacity {
+            // If the heap hasn't reached capacity we should always add the new item, and nothing
+            // is evicted
+            self.heap.push(item);
+            self.push_up(self.heap.len() - 1);
+            None
+        } else if (self.compare)(&item, &self.heap[0]) == Ordering::Greater {
+            // `item` beats the current minimum, so swap it in and report what got evicted
+            let evicted = std::mem::replace(&mut self.heap[0], item);
+            self.trickle_down(0);
+            Some(evicted)
         } else {
-            // If the heap is non-empty and `item` is less than this minimum we should early return
-            // without modifying the shortlist
-            if let Some(current_min) = self.heap.peek() {
-                if item <= current_min.0 {
-                    return;
-                }
-            }
-            // Since the heap is at capacity and `item` is bigger than the current table minimum,
-            // we have to remove the minimum value to make space for `item`
-            let popped = self.heap.pop();
-            debug_assert!(popped.is_some());
-            self.heap.push(Reverse(item));
+            // `item` isn't big enough to beat the current minimum, so hand it straight back
+            // without touching the heap
+            Some(item)
         }
     }
 
+    /// Attempts to add an item to the `Shortlist`, reporting whichever item ends up not making
+    /// the cut.
+    ///
+    /// This is an alias of [`Shortlist::push_replace`], named to mirror the `try_*` convention
+    /// used elsewhere (e.g. `Vec::try_reserve`) for operations that hand back whatever didn't fit,
+    /// so that callers scanning expensive-to-construct candidates can pair it with
+    /// [`Shortlist::threshold`]: skip scoring a candidate entirely if it can't beat the threshold,
+    /// and recycle whatever `try_push` hands back instead of allocating a fresh one. Since
+    /// `threshold` is `None` until the `Shortlist` is full, this check never skips a candidate
+    /// during the fill phase.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let mut shortlist = Shortlist::new(3);
+    /// shortlist.append([5, 1, 9]);
+    /// // `4` beats the current threshold (1), so it's admitted, evicting the `1`
+    /// let candidate = 4;
+    /// if shortlist.threshold().map_or(true, |&min| candidate > min) {
+    ///     assert_eq!(shortlist.try_push(candidate), Some(1));
+    /// }
+    /// ```
+    #[inline]
+    pub fn try_push(&mut self, item: T) -> Option<T> {
+        self.push_replace(item)
+    }
+
     /// Add an item to the `Shortlist` by reference, cloning it only if necessary.
     ///
     /// This is almost identical to [`Shortlist::push`], but gives better performance when cloning
@@ -310,23 +485,18 @@ impl<T: Ord> Shortlist<T> {
     where
         T: Clone,
     {
-        if self.heap.len() < self.heap.capacity() {
+        if self.heap.len() < self.capacity {
             // If the heap hasn't reached capacity we should always add the new item
-            self.heap.push(Reverse(item.clone()));
-        } else {
-            // If the heap is non-empty and `item` is less than this minimum we should early return
-            // without modifying the shortlist or cloning the item
-            if let Some(current_min) = self.heap.peek() {
-                if item <= &current_min.0 {
-                    return;
-                }
-            }
+            self.heap.push(item.clone());
+            self.push_up(self.heap.len() - 1);
+        } else if (self.compare)(item, &self.heap[0]) == Ordering::Greater {
             // Since the heap is at capacity and `item` is bigger than the current table minimum,
-            // we have to remove the minimum value to make space for `item`
-            let popped = self.heap.pop();
-            debug_assert!(popped.is_some());
-            self.heap.push(Reverse(item.clone()));
+            // we have to remove the minimum value to make space for `item`, only cloning `item`
+            // once we know it will actually be kept
+            self.heap[0] = item.clone();
+            self.trickle_down(0);
         }
+        // Otherwise, `item` isn't big enough to make the cut, so it isn't even cloned
     }
 
     /// Consume items from an iterator and add these to the `Shortlist`.
@@ -395,12 +565,6 @@ impl<T: Ord> Shortlist<T> {
     /// Consumes this `Shortlist` and return a [`Vec`] containing the contents of the `Shortlist` in
     /// ascending order.
     ///
-    /// # Safety
-    /// This uses one line of `unsafe` code to avoid allocating heap memory.
-    /// It makes no assumptions about the consumer's code and has been pretty extensively
-    /// tested, but if you still want to trade off the performance penalty to avoid using any
-    /// `unsafe` code, use [`Shortlist::into_sorted_vec_safe`] instead.
-    ///
     /// # Example
     /// ```
     /// use shortlist::Shortlist;
@@ -410,22 +574,19 @@ impl<T: Ord> Shortlist<T> {
     /// // The top 4 items of `contents` is [5, 6, 6, 7]
     /// assert_eq!(shortlist.into_sorted_vec(), vec![5, 6, 6, 7]);
     /// ```
-    pub fn into_sorted_vec(self) -> Vec<T> {
-        // We transmute the memory in order to convert the `Reverse<T>`s into `T`s without cloning
-        // the data.  This is fine because in memory, `Reverse<T>`s are identical to `T`s, so
-        // transmuting the `Vec` is completely allowed.
-        let mut vec: Vec<T> = unsafe { std::mem::transmute(self.heap.into_sorted_vec()) };
-        // Correct for the fact that the min-heap is actually a max-heap with the 'Ord' operations
-        // reversed.
-        vec.reverse();
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.heap.len());
+        while let Some(item) = self.pop_min() {
+            vec.push(item);
+        }
         vec
     }
 
     /// Consumes this `Shortlist` and return a [`Vec`] containing the contents of the `Shortlist`
     /// in ascending order.
     ///
-    /// This is an otherwise-identical version of [`into_sorted_vec`](Shortlist::into_sorted_vec)
-    /// that has no `unsafe` code at the cost of having to allocate heap memory.
+    /// This is kept as an alias of [`into_sorted_vec`](Shortlist::into_sorted_vec) for backwards
+    /// compatibility; the two are now identical, since neither needs `unsafe` code any more.
     ///
     /// # Example
     /// ```
@@ -436,16 +597,9 @@ impl<T: Ord> Shortlist<T> {
     /// // The top 4 items of `contents` is [5, 6, 6, 7]
     /// assert_eq!(shortlist.into_sorted_vec_safe(), vec![5, 6, 6, 7]);
     /// ```
+    #[inline]
     pub fn into_sorted_vec_safe(self) -> Vec<T> {
-        let mut reversed_vec = self.heap.into_sorted_vec();
-        // Correct for the fact that the min-heap is actually a max-heap with the 'Ord' operations
-        // reversed.
-        reversed_vec.reverse();
-        let mut vec = Vec::with_capacity(reversed_vec.len());
-        for i in reversed_vec.drain(..) {
-            vec.push(i.0);
-        }
-        vec
+        self.into_sorted_vec()
     }
 
     /// Returns a [`Vec`] containing the [`Clone`]d contents of this `Shortlist` in ascending
@@ -465,19 +619,11 @@ impl<T: Ord> Shortlist<T> {
     pub fn sorted_cloned_vec(&self) -> Vec<T>
     where
         T: Clone,
+        F: Clone,
     {
-        // We transmute the memory in order to convert the `Reverse<T>`s into `T`s without cloning
-        // the data.  This is fine because in memory, `Reverse<T>`s are identical to `T`s, so
-        // transmuting the `Vec` is completely allowed.
-        let mut vec: Vec<T> = unsafe { std::mem::transmute(self.heap.clone().into_sorted_vec()) };
-        // Correct for the fact that the min-heap is actually a max-heap with the 'Ord' operations
-        // reversed.
-        vec.reverse();
-        vec
+        self.clone().into_sorted_vec()
     }
-}
 
-impl<T> Shortlist<T> {
     /// Returns an [`Iterator`] that borrows the items in a `Shortlist`, in an arbitrary order.
     ///
     /// # Example
@@ -494,8 +640,8 @@ impl<T> Shortlist<T> {
     /// shortlist.push(3);
     /// ```
     #[inline]
-    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a {
-        self.heap.iter().map(|x| &x.0)
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.heap.iter()
     }
 
     /// Returns the maximum number of values that this `Shortlist` will store.
@@ -510,18 +656,12 @@ impl<T> Shortlist<T> {
     /// ```
     #[inline]
     pub fn capacity(&self) -> usize {
-        self.heap.capacity()
+        self.capacity
     }
 
     /// Consumes this `Shortlist` and return a [`Vec`] containing the contents of the `Shortlist`
     /// in an arbitrary order.
     ///
-    /// # Safety
-    /// This uses one line of `unsafe` code to avoid allocating heap memory.
-    /// It makes no assumptions about the consumer's code and has been pretty extensively
-    /// tested, but if you still want to trade off the performance penalty to avoid using any
-    /// `unsafe` code, use [`Shortlist::into_vec_safe`] instead.
-    ///
     /// # Example
     /// ```
     /// use shortlist::Shortlist;
@@ -533,18 +673,16 @@ impl<T> Shortlist<T> {
     /// top_4.sort();
     /// assert_eq!(top_4, vec![5, 6, 6, 7]);
     /// ```
+    #[inline]
     pub fn into_vec(self) -> Vec<T> {
-        // We transmute the memory in order to convert the `Reverse<T>`s into `T`s without cloning
-        // the data.  This is fine because in memory, `Reverse<T>`s are identical to `T`s, so
-        // transmuting the `Vec` is completely allowed.
-        unsafe { std::mem::transmute(self.heap.into_vec()) }
+        self.heap
     }
 
     /// Consumes this `Shortlist` and return a [`Vec`] containing the contents of the `Shortlist`
     /// in an arbitrary order.
     ///
-    /// This is an otherwise-identical version of [`into_vec`](Shortlist::into_vec) that has no
-    /// `unsafe` code at the cost of having to allocate heap memory.
+    /// This is kept as an alias of [`into_vec`](Shortlist::into_vec) for backwards compatibility;
+    /// the two are now identical, since neither needs `unsafe` code any more.
     ///
     /// # Example
     /// ```
@@ -557,14 +695,9 @@ impl<T> Shortlist<T> {
     /// top_4.sort();
     /// assert_eq!(top_4, vec![5, 6, 6, 7]);
     /// ```
+    #[inline]
     pub fn into_vec_safe(self) -> Vec<T> {
-        let mut reversed_vec = self.heap.into_vec();
-        // move all the values out of the `Reverse`s into a different vector, and return that
-        let mut vec = Vec::with_capacity(reversed_vec.len());
-        for i in reversed_vec.drain(..) {
-            vec.push(i.0);
-        }
-        vec
+        self.into_vec()
     }
 
     /// Returns the number of items in a `Shortlist`.
@@ -633,8 +766,8 @@ impl<T> Shortlist<T> {
     /// assert_eq!(drained_values, vec![3, 5, 5]);
     /// ```
     #[inline]
-    pub fn drain<'a>(&'a mut self) -> impl Iterator<Item = T> + 'a {
-        self.heap.drain().map(|x| x.0)
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.heap.drain(..)
     }
 
     /// Remove and drop all the items in a `Shortlist`, leaving it empty.
@@ -652,113 +785,1259 @@ impl<T> Shortlist<T> {
     pub fn clear(&mut self) {
         self.heap.clear();
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::Shortlist;
-    use rand::prelude::*;
 
-    /* ===== HELPER FUNCTIONS ===== */
+    /// Drains `other` into `self`, keeping the overall top [`capacity`](Shortlist::capacity)
+    /// items of the two `Shortlist`s combined.
+    ///
+    /// This is the natural way to combine the partial results of a parallelised brute-force
+    /// search: give each worker its own `Shortlist`, then `merge` the workers' `Shortlist`s
+    /// together (in any order) to get the global top-`capacity` items.
+    ///
+    /// `other`'s items are compared using `self`'s comparator, so `self` and `other` must share
+    /// the same comparator type `F` (they don't need equal `capacity`s, though `other`'s capacity
+    /// makes no difference to the result).
+    ///
+    /// # Time Complexity
+    /// `O(k log n)` where `k` is `other.len()` and `n` is `self.len()`, since this drains `other`
+    /// and pushes every item into `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// // Simulate two worker threads each shortlisting their own chunk of a search
+    /// let mut worker_a = Shortlist::from_slice(3, &[1, 5, 2]);
+    /// let worker_b = Shortlist::from_slice(3, &[9, 0, 4]);
+    /// // Combine the two workers' results into the global top 3
+    /// worker_a.merge(worker_b);
+    /// assert_eq!(worker_a.into_sorted_vec(), vec![4, 5, 9]);
+    /// ```
+    pub fn merge(&mut self, other: Shortlist<T, F>) {
+        for item in other.heap {
+            self.push(item);
+        }
+    }
 
-    /// Given a sorted [`Vec`] of input values and a sorted [`Vec`] of the values taken from a
-    /// [`Shortlist`] of those items, checks that the [`Shortlist`] behaved correctly.
-    fn check_sorted_vecs<T: Ord + Eq + std::fmt::Debug>(
-        sorted_input_values: Vec<T>,
-        shortlist_vec: Vec<T>,
-        capacity: usize,
-    ) {
-        let mut debug_lines = Vec::with_capacity(1000);
-        debug_lines.push("".to_string());
-        debug_lines.push(format!("Input length      : {}", sorted_input_values.len()));
-        debug_lines.push(format!("Shortlist capacity: {}", capacity));
-        debug_lines.push(format!("Shortlist length  : {}", shortlist_vec.len()));
-        // let shortlist_vec = shortlist.into_sorted_vec();
-        // Check that the shortlist's length is the minimum of its capacity and the number of input
-        // values
-        if shortlist_vec.len() != capacity.min(sorted_input_values.len()) {
-            debug_lines.push(format!("Input values: {:?}", sorted_input_values));
-            debug_lines.push(format!("Shortlisted values: {:?}", shortlist_vec));
-            // Print the debug info before panicking
-            for line in debug_lines {
-                println!("{}", line);
-            }
-            panic!();
+    /// Merges several `Shortlist`s into `self`, keeping the overall top
+    /// [`capacity`](Shortlist::capacity) items across all of them combined.
+    ///
+    /// This is equivalent to calling [`Shortlist::merge`] on every `Shortlist` yielded by
+    /// `others`, and is the natural "reduce" step for a map-reduce style parallel search that
+    /// shortlists each chunk independently.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let mut combined: Shortlist<i32> = Shortlist::new(3);
+    /// let workers = vec![
+    ///     Shortlist::from_slice(3, &[1, 5, 2]),
+    ///     Shortlist::from_slice(3, &[9, 0, 4]),
+    ///     Shortlist::from_slice(3, &[3, 8, 6]),
+    /// ];
+    /// combined.merge_from_iter(workers);
+    /// assert_eq!(combined.into_sorted_vec(), vec![6, 8, 9]);
+    /// ```
+    pub fn merge_from_iter(&mut self, others: impl IntoIterator<Item = Shortlist<T, F>>) {
+        for other in others {
+            self.merge(other);
         }
-        // Check that `shortlist.into_sorted_vec()` produces a suffix of `input_values` (we can
-        // guaruntee that the input values are sorted).
-        for (val, exp_val) in shortlist_vec
-            .iter()
-            .rev()
-            .zip(sorted_input_values.iter().rev())
-        {
-            if val == exp_val {
-                debug_lines.push(format!("{:?} == {:?}", val, exp_val));
+    }
+
+    /// Removes every item that doesn't satisfy `pred`, re-establishing the heap invariant
+    /// in-place.
+    ///
+    /// This is useful for multi-stage searches that apply a cheap filter first and a more
+    /// expensive validity check later: the expensive check can run only over the already-small
+    /// shortlisted set, discarding whatever fails it.
+    ///
+    /// `capacity` is unchanged, but `len` may shrink below it; the freed slots are reclaimed by
+    /// subsequent [`push`](Shortlist::push) calls without any new allocation.
+    ///
+    /// # Time Complexity
+    /// `O(n)`, where `n` is `self.len()`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let mut shortlist = Shortlist::from_slice(5, &[3, 1, 4, 1, 5, 9, 2, 6]);
+    /// // The top 5 values are [3, 4, 5, 6, 9]
+    /// assert_eq!(shortlist.len(), 5);
+    /// // Discard the even numbers from the shortlist
+    /// shortlist.retain(|&item| item % 2 == 1);
+    /// assert_eq!(shortlist.sorted_cloned_vec(), vec![3, 5, 9]);
+    /// // The freed slots can be refilled
+    /// shortlist.push(100);
+    /// shortlist.push(7);
+    /// assert_eq!(shortlist.sorted_cloned_vec(), vec![3, 5, 7, 9, 100]);
+    /// ```
+    pub fn retain(&mut self, mut pred: impl FnMut(&T) -> bool) {
+        self.heap.retain(|item| pred(item));
+        self.heapify();
+    }
+
+    /// Removes every item that satisfies `pred`, re-establishing the heap invariant over whatever
+    /// remains, and returns the removed items in arbitrary (heap) order.
+    ///
+    /// This is the inverse of [`Shortlist::retain`] (which keeps what satisfies `pred` and
+    /// discards the rest): use `drain_filter` when the removed items themselves are needed, e.g.
+    /// when a later-discovered constraint disqualifies some top-k entries but they still need to
+    /// be logged, re-scored, or fed into a secondary pass.
+    ///
+    /// `capacity` is unchanged, but `len` may shrink below it; the freed slots are reclaimed by
+    /// subsequent [`push`](Shortlist::push) calls without any new allocation.
+    ///
+    /// # Time Complexity
+    /// `O(n)`, where `n` is `self.len()`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let mut shortlist = Shortlist::from_slice(5, &[3, 1, 4, 1, 5, 9, 2, 6]);
+    /// // The top 5 values are [3, 4, 5, 6, 9]
+    /// assert_eq!(shortlist.len(), 5);
+    /// // Disqualify the even numbers, but keep hold of them
+    /// let mut disqualified = shortlist.drain_filter(|&item| item % 2 == 0);
+    /// disqualified.sort();
+    /// assert_eq!(disqualified, vec![4, 6]);
+    /// assert_eq!(shortlist.sorted_cloned_vec(), vec![3, 5, 9]);
+    /// // The freed slots can be refilled
+    /// shortlist.push(100);
+    /// shortlist.push(7);
+    /// assert_eq!(shortlist.sorted_cloned_vec(), vec![3, 5, 7, 9, 100]);
+    /// ```
+    pub fn drain_filter(&mut self, mut pred: impl FnMut(&T) -> bool) -> Vec<T> {
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(self.heap.len());
+        for item in self.heap.drain(..) {
+            if pred(&item) {
+                removed.push(item);
             } else {
-                debug_lines.push(format!("{:?} != {:?}", val, exp_val));
-                // Print the debug info before panicking
-                for line in debug_lines {
-                    println!("{}", line);
-                }
-                panic!();
+                kept.push(item);
             }
         }
+        self.heap = kept;
+        self.heapify();
+        removed
     }
 
-    /// Generates a random capacity and randomised input [`Vec`] to be used as a test sample.
-    fn gen_sample_input(rng: &mut impl Rng) -> (usize, Vec<usize>) {
-        // Decide how much capacity the shortlist will have
-        let capacity = rng.gen_range(1, 100);
-        // Make empty collections
-        let mut input_values: Vec<usize> = Vec::new();
-        // Populate both collections with the same values
-        for _ in 0..rng.gen_range(1, 1000) {
-            let val = rng.gen_range(0, 1000);
-            input_values.push(val);
+    /// Returns the current eviction threshold: the value that any new item must beat in order to
+    /// be admitted by [`push`](Shortlist::push), or `None` if the `Shortlist` isn't full yet (in
+    /// which case every item is admitted, regardless of its value).
+    ///
+    /// Named for the common use case of reading the threshold to cheaply skip scoring a candidate
+    /// that provably can't beat it, without ever constructing the candidate's `T`. Unlike
+    /// [`Shortlist::peek_min`], which reports the smallest held item at any fill level, this
+    /// returns `None` until `len() == capacity()` so that the skip-optimization above is always
+    /// safe to apply.
+    ///
+    /// # Time Complexity
+    /// `O(1)`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let mut shortlist = Shortlist::new(3);
+    /// shortlist.append([10, 20]);
+    /// // Not full yet, so every candidate is still admitted, regardless of value
+    /// assert_eq!(shortlist.threshold(), None);
+    ///
+    /// shortlist.push(30);
+    /// // Now full: only candidates beating the current minimum (10) are admitted
+    /// assert_eq!(shortlist.threshold(), Some(&10));
+    /// ```
+    #[inline]
+    pub fn threshold(&self) -> Option<&T> {
+        if self.heap.len() < self.capacity {
+            None
+        } else {
+            self.peek_min()
         }
-        (capacity, input_values)
     }
 
-    /// Generates a randomised chunk of input data and a [`Shortlist`] built from that data.  The
-    /// [`Vec`] returned is always sorted, though the [`Shortlist`] is generated from the unsorted
-    /// data to be a fair test.
-    fn generate_input_and_shortlist(rng: &mut impl Rng) -> (Vec<usize>, Shortlist<usize>) {
-        let (capacity, mut input_values) = gen_sample_input(rng);
-        let shortlist: Shortlist<usize> = Shortlist::from_slice(capacity, &input_values);
-        // Sort the input values and return
-        input_values.sort();
-        (input_values, shortlist)
+    /// Returns the smallest item currently in the `Shortlist`, or `None` if it is empty.
+    ///
+    /// # Time Complexity
+    /// `O(1)`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let shortlist = Shortlist::from_slice(3, &[5, 1, 9, 2, 7]);
+    /// // The top 3 values are [5, 7, 9], of which the smallest is 5
+    /// assert_eq!(shortlist.peek_min(), Some(&5));
+    /// ```
+    #[inline]
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.first()
     }
 
-    /// Test a given check over [`Shortlist`]s many many times.
-    fn check_correctness(check: impl Fn(Vec<usize>, Shortlist<usize>) -> ()) {
-        let mut rng = thread_rng();
-        // Make a shortlist with a known set of values
-        for _ in 1..10_000 {
-            let (input_values, shortlist) = generate_input_and_shortlist(&mut rng);
-            // Check that the shortlist contains a suffix of the sorted reference vec
-            check(input_values, shortlist);
+    /// Returns the largest item currently in the `Shortlist`, or `None` if it is empty.
+    ///
+    /// # Time Complexity
+    /// `O(1)`.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let shortlist = Shortlist::from_slice(3, &[5, 1, 9, 2, 7]);
+    /// // The top 3 values are [5, 7, 9], of which the largest is 9
+    /// assert_eq!(shortlist.peek_max(), Some(&9));
+    /// ```
+    pub fn peek_max(&self) -> Option<&T> {
+        match (self.heap.get(1), self.heap.get(2)) {
+            (None, _) => self.heap.first(),
+            (Some(a), None) => Some(a),
+            (Some(a), Some(b)) => {
+                if (self.compare)(a, b) == Ordering::Less {
+                    Some(b)
+                } else {
+                    Some(a)
+                }
+            }
         }
     }
 
-    /* ===== TESTING FUNCTIONS ===== */
+    /// Restores the heap invariant over the whole of `self.heap`, assuming it may currently be in
+    /// arbitrary order.
+    ///
+    /// This relies on being called bottom-up (from the last internal node to the root), so that
+    /// `trickle_down(i)` can assume both of `i`'s subtrees are already valid min-max heaps.
+    fn heapify(&mut self) {
+        for i in (0..self.heap.len() / 2).rev() {
+            self.trickle_down(i);
+        }
+    }
 
-    #[test]
-    fn iter() {
-        check_correctness(|values, shortlist| {
-            // Store the capacity for both tests to use
-            let capacity = shortlist.capacity();
-            // Unload the Shortlist using `Shortlist::iter`
-            let mut shortlist_vec: Vec<usize> = shortlist.iter().copied().collect();
-            shortlist_vec.sort();
-            check_sorted_vecs(values, shortlist_vec, capacity);
-        });
+    /// Removes the current minimum item from the heap (if any), restoring the heap invariant.
+    fn pop_min(&mut self) -> Option<T> {
+        self.remove_at(0)
     }
 
-    #[test]
-    fn into_sorted_vec() {
-        check_correctness(|values, shortlist| {
-            let capacity = shortlist.capacity();
+    /// Removes the current maximum item from the heap (if any), restoring the heap invariant.
+    fn pop_max(&mut self) -> Option<T> {
+        let i = match (self.heap.get(1), self.heap.get(2)) {
+            (None, _) => 0,
+            (Some(_), None) => 1,
+            (Some(a), Some(b)) => {
+                if (self.compare)(a, b) == Ordering::Less {
+                    2
+                } else {
+                    1
+                }
+            }
+        };
+        self.remove_at(i)
+    }
+
+    /// Removes the item at index `i`, moving the last item into its place and restoring the heap
+    /// invariant.
+    ///
+    /// This is only ever used to remove the current min (index `0`) or max (index `1` or `2`), so
+    /// the item that moves into `i`'s place is already known to be within `i`'s subtree's bounds
+    /// with respect to `i`'s ancestors (every other node in a valid min-max heap is already
+    /// sandwiched between the global min at the root and the global max), and so only ever needs
+    /// to trickle downwards, never back up.
+    fn remove_at(&mut self, i: usize) -> Option<T> {
+        if i >= self.heap.len() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.heap.swap(i, last);
+        let item = self.heap.pop();
+        if i < self.heap.len() {
+            self.trickle_down(i);
+        }
+        item
+    }
+
+    /// Returns the min-max heap 'level' of index `i`, where the root is level `0`.  Levels
+    /// alternate between being ordered as a min-heap (even levels) and a max-heap (odd levels).
+    fn level(i: usize) -> u32 {
+        (usize::BITS - 1) - (i + 1).leading_zeros()
+    }
+
+    /// Moves the item at index `i` towards the root until the heap invariant is restored.
+    fn push_up(&mut self, i: usize) {
+        if i == 0 {
+            return;
+        }
+        let parent = (i - 1) / 2;
+        if Self::level(i) % 2 == 0 {
+            // `i` is on a min level: it should be <= its parent (which is on a max level)
+            if (self.compare)(&self.heap[i], &self.heap[parent]) == Ordering::Greater {
+                self.heap.swap(i, parent);
+                self.push_up_max(parent);
+            } else {
+                self.push_up_min(i);
+            }
+        } else {
+            // `i` is on a max level: it should be >= its parent (which is on a min level)
+            if (self.compare)(&self.heap[i], &self.heap[parent]) == Ordering::Less {
+                self.heap.swap(i, parent);
+                self.push_up_min(parent);
+            } else {
+                self.push_up_max(i);
+            }
+        }
+    }
+
+    /// Moves the item at index `i` (known to be on a min level) up past its grandparents for as
+    /// long as it is smaller than them.
+    fn push_up_min(&mut self, mut i: usize) {
+        while i >= 3 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if (self.compare)(&self.heap[i], &self.heap[grandparent]) == Ordering::Less {
+                self.heap.swap(i, grandparent);
+                i = grandparent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Moves the item at index `i` (known to be on a max level) up past its grandparents for as
+    /// long as it is bigger than them.
+    fn push_up_max(&mut self, mut i: usize) {
+        while i >= 3 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if (self.compare)(&self.heap[i], &self.heap[grandparent]) == Ordering::Greater {
+                self.heap.swap(i, grandparent);
+                i = grandparent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Moves the item at index `i` towards the leaves until the heap invariant is restored.
+    fn trickle_down(&mut self, i: usize) {
+        if Self::level(i) % 2 == 0 {
+            self.trickle_down_min(i);
+        } else {
+            self.trickle_down_max(i);
+        }
+    }
+
+    /// Moves the item at index `i` (known to be on a min level) down towards the smallest of its
+    /// children and grandchildren until the heap invariant is restored.
+    fn trickle_down_min(&mut self, i: usize) {
+        let Some(m) = self.smallest_descendant(i) else {
+            return;
+        };
+        if (self.compare)(&self.heap[m], &self.heap[i]) == Ordering::Less {
+            self.heap.swap(m, i);
+            if Self::is_grandchild(i, m) {
+                let parent = (m - 1) / 2;
+                if (self.compare)(&self.heap[m], &self.heap[parent]) == Ordering::Greater {
+                    self.heap.swap(m, parent);
+                }
+                self.trickle_down_min(m);
+            }
+        }
+    }
+
+    /// Moves the item at index `i` (known to be on a max level) down towards the largest of its
+    /// children and grandchildren until the heap invariant is restored.
+    fn trickle_down_max(&mut self, i: usize) {
+        let Some(m) = self.largest_descendant(i) else {
+            return;
+        };
+        if (self.compare)(&self.heap[m], &self.heap[i]) == Ordering::Greater {
+            self.heap.swap(m, i);
+            if Self::is_grandchild(i, m) {
+                let parent = (m - 1) / 2;
+                if (self.compare)(&self.heap[m], &self.heap[parent]) == Ordering::Less {
+                    self.heap.swap(m, parent);
+                }
+                self.trickle_down_max(m);
+            }
+        }
+    }
+
+    /// Returns the index, among `i`'s children and grandchildren that exist, of the smallest one.
+    fn smallest_descendant(&self, i: usize) -> Option<usize> {
+        Self::descendants(i, self.heap.len())
+            .into_iter()
+            .flatten()
+            .min_by(|&a, &b| (self.compare)(&self.heap[a], &self.heap[b]))
+    }
+
+    /// Returns the index, among `i`'s children and grandchildren that exist, of the largest one.
+    fn largest_descendant(&self, i: usize) -> Option<usize> {
+        Self::descendants(i, self.heap.len())
+            .into_iter()
+            .flatten()
+            .max_by(|&a, &b| (self.compare)(&self.heap[a], &self.heap[b]))
+    }
+
+    /// Returns the (up to 2 children + 4 grandchildren) indices of `i` that are `< len`.
+    fn descendants(i: usize, len: usize) -> [Option<usize>; 6] {
+        let children = [2 * i + 1, 2 * i + 2];
+        let grandchildren = [4 * i + 3, 4 * i + 4, 4 * i + 5, 4 * i + 6];
+        let mut out = [None; 6];
+        for (slot, idx) in out.iter_mut().zip(children.into_iter().chain(grandchildren)) {
+            *slot = (idx < len).then_some(idx);
+        }
+        out
+    }
+
+    /// Returns `true` if `descendant` is a grandchild (rather than a child) of `ancestor`.
+    fn is_grandchild(ancestor: usize, descendant: usize) -> bool {
+        descendant > 2 * ancestor + 2
+    }
+
+    /// Consumes this `Shortlist` and returns an [`Iterator`] that lazily yields its items in
+    /// **descending** order (the best-shortlisted item first), without materializing a full
+    /// [`Vec`] up front.
+    ///
+    /// Each item is produced in `O(log n)` by popping the current maximum, so consumers that only
+    /// want e.g. the best few items (or who want to stream the results) only pay for what they
+    /// consume. If you want every item, eagerly collecting into a [`Vec`] with
+    /// [`Shortlist::into_sorted_vec`] and reversing it will usually be faster.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    ///
+    /// let shortlist = Shortlist::from_slice(5, &[3, 1, 4, 1, 5, 9, 2, 6]);
+    /// // The top 5 values are [3, 4, 5, 6, 9]; only consume the two biggest of them
+    /// let biggest_two: Vec<i32> = shortlist.into_iter_sorted().take(2).collect();
+    /// assert_eq!(biggest_two, vec![9, 6]);
+    /// ```
+    #[inline]
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, F> {
+        IntoIterSorted { shortlist: self }
+    }
+}
+
+/// A lazy [`Iterator`] over the items of a [`Shortlist`] in descending order, created by
+/// [`Shortlist::into_iter_sorted`] (or by using a [`Shortlist`] directly as an [`IntoIterator`]).
+#[derive(Debug, Clone)]
+pub struct IntoIterSorted<T, F = fn(&T, &T) -> Ordering> {
+    shortlist: Shortlist<T, F>,
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Iterator for IntoIterSorted<T, F> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.shortlist.pop_max()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.shortlist.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> ExactSizeIterator for IntoIterSorted<T, F> {}
+
+/// Consumes the `Shortlist`, yielding its items in descending order; see
+/// [`Shortlist::into_iter_sorted`].
+impl<T, F: Fn(&T, &T) -> Ordering> IntoIterator for Shortlist<T, F> {
+    type Item = T;
+    type IntoIter = IntoIterSorted<T, F>;
+
+    #[inline]
+    fn into_iter(self) -> IntoIterSorted<T, F> {
+        self.into_iter_sorted()
+    }
+}
+
+/// Orders `(key, item)` pairs purely by their A-Res `key`, used as the comparator that backs a
+/// [`WeightedSample`]'s heap. A plain `fn` item (rather than a closure) so it coerces to the
+/// concrete, non-capturing `fn` pointer type that [`WeightedSample`] names in its field.
+#[cfg(feature = "rand")]
+fn weighted_key_cmp<T>(a: &(f64, T), b: &(f64, T)) -> Ordering {
+    a.0.partial_cmp(&b.0).expect("A-Res keys are always finite")
+}
+
+/// A weighted random sample of up to `capacity` items, created by [`Shortlist::sample_weighted`]
+/// and populated via [`WeightedSample::push_weighted`].
+///
+/// Each pushed item is retained with probability proportional to its weight, using the
+/// Efraimidis–Spirakis A-Res algorithm: every item is assigned a random key `u.powf(1.0 / weight)`
+/// for `u` drawn uniformly from `[0, 1)`, and the `capacity` items with the largest keys are kept —
+/// reusing the same bounded min-max heap machinery that backs [`Shortlist`] itself, just ordered by
+/// key instead of by the items.
+///
+/// Requires the `rand` feature.
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone)]
+pub struct WeightedSample<T> {
+    heap: WeightedHeap<T>,
+}
+
+/// The comparator-specialized [`Shortlist`] that backs a [`WeightedSample`]'s heap, ordering
+/// `(key, item)` pairs by their A-Res `key` via [`weighted_key_cmp`].
+#[cfg(feature = "rand")]
+type WeightedHeap<T> = Shortlist<(f64, T), fn(&(f64, T), &(f64, T)) -> Ordering>;
+
+#[cfg(feature = "rand")]
+impl<T> WeightedSample<T> {
+    /// Offers `item` to the sample with the given `weight`, drawing randomness from `rng`.
+    ///
+    /// A `weight` of `0.0` is never retained, and non-finite weights (`NaN` or infinite) are
+    /// rejected outright, since A-Res's key computation is undefined for them. If every item is
+    /// pushed with the same (finite, positive) weight, this reduces to uniform reservoir sampling
+    /// of `capacity` items from the stream.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let mut sample = Shortlist::sample_weighted(2);
+    /// for item in 0..100 {
+    ///     sample.push_weighted(item, 1.0, &mut rng);
+    /// }
+    /// assert_eq!(sample.len(), 2);
+    /// ```
+    pub fn push_weighted(&mut self, item: T, weight: f64, rng: &mut impl rand::Rng) {
+        if !(weight.is_finite() && weight > 0.0) {
+            return;
+        }
+        let u: f64 = rng.gen_range(0.0, 1.0);
+        let key = u.powf(1.0 / weight);
+        self.heap.push((key, item));
+    }
+
+    /// Returns the maximum number of items this sample will retain.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    /// Returns the number of items currently in the sample.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the sample currently contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Consumes the sample, returning its items in arbitrary order (the A-Res keys are discarded).
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let mut sample = Shortlist::sample_weighted(1);
+    /// sample.push_weighted("only item", 1.0, &mut rng);
+    /// assert_eq!(sample.into_vec(), vec!["only item"]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+            .into_vec()
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// Removes every item from the sample, returning them in arbitrary order (the A-Res keys are
+    /// discarded), leaving `self` empty so it can be reused to sample another stream.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.heap.drain().map(|(_, item)| item)
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<T> Shortlist<T> {
+    /// Creates an empty [`WeightedSample`] that retains up to `capacity` items, each kept with
+    /// probability proportional to the weight it is pushed with; see [`WeightedSample`] for the
+    /// details of the sampling algorithm.
+    ///
+    /// Requires the `rand` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::Shortlist;
+    /// use rand::SeedableRng;
+    /// use rand::rngs::StdRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(0);
+    /// let mut sample = Shortlist::sample_weighted(10);
+    /// sample.push_weighted("a", 1.0, &mut rng);
+    /// assert_eq!(sample.len(), 1);
+    /// ```
+    pub fn sample_weighted(capacity: usize) -> WeightedSample<T> {
+        WeightedSample {
+            heap: Shortlist::new_by(
+                capacity,
+                weighted_key_cmp::<T> as fn(&(f64, T), &(f64, T)) -> Ordering,
+            ),
+        }
+    }
+}
+
+/// Fixed-capacity backing storage for a [`SmallShortlist`]: either `N` inline slots (no heap
+/// allocation) or a spilled [`Vec`].  Which variant is used is decided once, when the
+/// `SmallShortlist` is created, depending on whether its capacity is `<= N`.
+#[derive(Debug, Clone)]
+enum Storage<T, const N: usize> {
+    Inline { items: [Option<T>; N], len: usize },
+    Spilled(Vec<T>),
+}
+
+impl<T, const N: usize> Storage<T, N> {
+    fn new(capacity: usize) -> Storage<T, N> {
+        if capacity <= N {
+            Storage::Inline {
+                items: std::array::from_fn(|_| None),
+                len: 0,
+            }
+        } else {
+            Storage::Spilled(Vec::with_capacity(capacity))
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Storage::Inline { len, .. } => *len,
+            Storage::Spilled(v) => v.len(),
+        }
+    }
+
+    fn get(&self, i: usize) -> Option<&T> {
+        match self {
+            Storage::Inline { items, len } if i < *len => items[i].as_ref(),
+            Storage::Inline { .. } => None,
+            Storage::Spilled(v) => v.get(i),
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        match self {
+            Storage::Inline { items, len } => {
+                items[*len] = Some(item);
+                *len += 1;
+            }
+            Storage::Spilled(v) => v.push(item),
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        match self {
+            Storage::Inline { items, len } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                items[*len].take()
+            }
+            Storage::Spilled(v) => v.pop(),
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        match self {
+            Storage::Inline { items, .. } => items.swap(a, b),
+            Storage::Spilled(v) => v.swap(a, b),
+        }
+    }
+
+    fn retain(&mut self, mut pred: impl FnMut(&T) -> bool) {
+        match self {
+            Storage::Inline { items, len } => {
+                let mut write = 0;
+                for read in 0..*len {
+                    if pred(items[read].as_ref().unwrap()) {
+                        if write != read {
+                            items.swap(write, read);
+                        }
+                        write += 1;
+                    }
+                }
+                for slot in &mut items[write..*len] {
+                    *slot = None;
+                }
+                *len = write;
+            }
+            Storage::Spilled(v) => v.retain(|item| pred(item)),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Storage::Inline { items, len } => {
+                for slot in &mut items[..*len] {
+                    *slot = None;
+                }
+                *len = 0;
+            }
+            Storage::Spilled(v) => v.clear(),
+        }
+    }
+
+}
+
+impl<T, const N: usize> std::ops::Index<usize> for Storage<T, N> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        self.get(i).expect("Storage index out of bounds")
+    }
+}
+
+impl<T, const N: usize> std::ops::IndexMut<usize> for Storage<T, N> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        match self {
+            Storage::Inline { items, len } => {
+                assert!(i < *len, "Storage index out of bounds");
+                items[i].as_mut().unwrap()
+            }
+            Storage::Spilled(v) => &mut v[i],
+        }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Storage<T, N> {
+    type Item = T;
+    type IntoIter = StorageIntoIter<T, N>;
+
+    fn into_iter(self) -> StorageIntoIter<T, N> {
+        match self {
+            Storage::Inline { items, len } => {
+                StorageIntoIter::Inline(items.into_iter().take(len))
+            }
+            Storage::Spilled(v) => StorageIntoIter::Spilled(v.into_iter()),
+        }
+    }
+}
+
+/// The owning iterator over a [`Storage`], yielding its items by value.
+enum StorageIntoIter<T, const N: usize> {
+    Inline(std::iter::Take<std::array::IntoIter<Option<T>, N>>),
+    Spilled(std::vec::IntoIter<T>),
+}
+
+impl<T, const N: usize> Iterator for StorageIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            StorageIntoIter::Inline(it) => it.next().map(|item| item.unwrap()),
+            StorageIntoIter::Spilled(it) => it.next(),
+        }
+    }
+}
+
+/// A [`Shortlist`]-alike that stores up to `N` items inline, making no heap allocations as long as
+/// its capacity is at most `N`; capacities bigger than `N` transparently spill over to a heap
+/// allocated buffer, exactly like [`Shortlist`] itself.
+///
+/// This is intended for the common case of small shortlists (top-5, top-10, ...) that get created
+/// in a hot loop (e.g. per-row top-k in a search), where paying for a heap allocation on every
+/// [`SmallShortlist::new`] would dominate the cost of the search itself.
+///
+/// Unlike [`Shortlist`], items are always ordered by their own [`Ord`] implementation; there is no
+/// equivalent of [`Shortlist::new_by`]/[`Shortlist::new_by_key`].
+///
+/// # Example
+/// ```
+/// use shortlist::SmallShortlist;
+///
+/// // Creating and filling the shortlist makes no heap allocation, since the capacity (3) is <= N
+/// // (8); only `into_sorted_vec` allocates, for the `Vec` it returns
+/// let mut shortlist: SmallShortlist<u32, 8> = SmallShortlist::new(3);
+/// shortlist.append_slice(&[1, 5, 2, 9, 3, 7]);
+/// assert_eq!(shortlist.into_sorted_vec(), vec![5, 7, 9]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmallShortlist<T, const N: usize> {
+    heap: Storage<T, N>,
+    capacity: usize,
+}
+
+impl<T: Ord, const N: usize> SmallShortlist<T, N> {
+    /// Creates a new empty `SmallShortlist` with a given capacity.
+    ///
+    /// If `capacity <= N`, `self` is stored entirely inline and this (and every subsequent
+    /// operation on the `SmallShortlist` except [`into_sorted_vec`](SmallShortlist::into_sorted_vec),
+    /// which allocates the `Vec` it returns) makes no heap allocations at all. Otherwise, `self`
+    /// spills over to a heap-allocated buffer, exactly like [`Shortlist::new`].
+    ///
+    /// # Panics
+    /// Creating a `SmallShortlist` with capacity `0` is a logical error and will cause a panic.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::SmallShortlist;
+    ///
+    /// let shortlist: SmallShortlist<u64, 4> = SmallShortlist::new(4);
+    /// assert_eq!(shortlist.capacity(), 4);
+    /// assert!(shortlist.is_empty());
+    /// ```
+    pub fn new(capacity: usize) -> SmallShortlist<T, N> {
+        assert!(capacity > 0, "Cannot create a SmallShortlist with capacity 0.");
+        SmallShortlist {
+            heap: Storage::new(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds an item to the `SmallShortlist`, discarding whichever item (if any) doesn't make the
+    /// cut; see [`Shortlist::push`].
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::SmallShortlist;
+    ///
+    /// let mut shortlist: SmallShortlist<u32, 8> = SmallShortlist::new(2);
+    /// shortlist.push(1);
+    /// shortlist.push(5);
+    /// shortlist.push(3);
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![3, 5]);
+    /// ```
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.capacity {
+            self.heap.push(item);
+            self.push_up(self.heap.len() - 1);
+        } else if item.cmp(&self.heap[0]) == Ordering::Greater {
+            self.heap[0] = item;
+            self.trickle_down(0);
+        }
+    }
+
+    /// Clones every item from a slice and adds them to the `SmallShortlist`; see
+    /// [`Shortlist::append_slice`].
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::SmallShortlist;
+    ///
+    /// let mut shortlist: SmallShortlist<u32, 8> = SmallShortlist::new(3);
+    /// shortlist.append_slice(&[0, 4, 3, 2, 5]);
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![3, 4, 5]);
+    /// ```
+    pub fn append_slice(&mut self, contents: &[T])
+    where
+        T: Clone,
+    {
+        for item in contents {
+            self.push(item.clone());
+        }
+    }
+
+    /// Returns the maximum number of items that this `SmallShortlist` will store at any one time.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of items currently in the `SmallShortlist`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the `SmallShortlist` currently contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.len() == 0
+    }
+
+    /// Removes every item from the `SmallShortlist`, leaving it empty (but keeping its capacity
+    /// and, in particular, whether it has already spilled over to a heap allocation).
+    #[inline]
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Removes every item that doesn't satisfy `pred`, re-establishing the heap invariant
+    /// in-place; see [`Shortlist::retain`].
+    ///
+    /// `capacity` is unchanged, but `len` may shrink below it; the freed slots are reclaimed by
+    /// subsequent [`push`](SmallShortlist::push) calls without any new allocation.
+    ///
+    /// # Example
+    /// ```
+    /// use shortlist::SmallShortlist;
+    ///
+    /// let mut shortlist: SmallShortlist<u32, 8> = SmallShortlist::new(5);
+    /// shortlist.append_slice(&[3, 1, 4, 1, 5, 9, 2, 6]);
+    /// // The top 5 values are [3, 4, 5, 6, 9]
+    /// shortlist.retain(|&item| item % 2 == 1);
+    /// assert_eq!(shortlist.into_sorted_vec(), vec![3, 5, 9]);
+    /// ```
+    pub fn retain(&mut self, pred: impl FnMut(&T) -> bool) {
+        self.heap.retain(pred);
+        self.heapify();
+    }
+
+    /// Removes and returns every item from the `SmallShortlist`, in arbitrary (heap) order; see
+    /// [`Shortlist::drain`].
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        let old_heap = std::mem::replace(&mut self.heap, Storage::new(self.capacity));
+        old_heap.into_iter()
+    }
+
+    /// Consumes the `SmallShortlist` and returns a [`Vec`] containing its contents in ascending
+    /// order; see [`Shortlist::into_sorted_vec`].
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut vec = Vec::with_capacity(self.heap.len());
+        while let Some(item) = self.pop_min() {
+            vec.push(item);
+        }
+        vec
+    }
+
+    /// Removes the current minimum item from the heap (if any), restoring the heap invariant.
+    fn pop_min(&mut self) -> Option<T> {
+        let last = self.heap.len().checked_sub(1)?;
+        self.heap.swap(0, last);
+        let item = self.heap.pop();
+        if self.heap.len() > 0 {
+            self.trickle_down(0);
+        }
+        item
+    }
+
+    /// Restores the heap invariant over the whole of `self.heap`; see [`Shortlist::heapify`].
+    fn heapify(&mut self) {
+        for i in (0..self.heap.len() / 2).rev() {
+            self.trickle_down(i);
+        }
+    }
+
+    /// Returns the min-max heap 'level' of index `i`; see [`Shortlist::level`].
+    fn level(i: usize) -> u32 {
+        (usize::BITS - 1) - (i + 1).leading_zeros()
+    }
+
+    fn push_up(&mut self, i: usize) {
+        if i == 0 {
+            return;
+        }
+        let parent = (i - 1) / 2;
+        if Self::level(i) % 2 == 0 {
+            if self.heap[i].cmp(&self.heap[parent]) == Ordering::Greater {
+                self.heap.swap(i, parent);
+                self.push_up_max(parent);
+            } else {
+                self.push_up_min(i);
+            }
+        } else if self.heap[i].cmp(&self.heap[parent]) == Ordering::Less {
+            self.heap.swap(i, parent);
+            self.push_up_min(parent);
+        } else {
+            self.push_up_max(i);
+        }
+    }
+
+    fn push_up_min(&mut self, mut i: usize) {
+        while i >= 3 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if self.heap[i].cmp(&self.heap[grandparent]) == Ordering::Less {
+                self.heap.swap(i, grandparent);
+                i = grandparent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn push_up_max(&mut self, mut i: usize) {
+        while i >= 3 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if self.heap[i].cmp(&self.heap[grandparent]) == Ordering::Greater {
+                self.heap.swap(i, grandparent);
+                i = grandparent;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn trickle_down(&mut self, i: usize) {
+        if Self::level(i) % 2 == 0 {
+            self.trickle_down_min(i);
+        } else {
+            self.trickle_down_max(i);
+        }
+    }
+
+    fn trickle_down_min(&mut self, i: usize) {
+        let Some(m) = self.smallest_descendant(i) else {
+            return;
+        };
+        if self.heap[m].cmp(&self.heap[i]) == Ordering::Less {
+            self.heap.swap(m, i);
+            if Self::is_grandchild(i, m) {
+                let parent = (m - 1) / 2;
+                if self.heap[m].cmp(&self.heap[parent]) == Ordering::Greater {
+                    self.heap.swap(m, parent);
+                }
+                self.trickle_down_min(m);
+            }
+        }
+    }
+
+    fn trickle_down_max(&mut self, i: usize) {
+        let Some(m) = self.largest_descendant(i) else {
+            return;
+        };
+        if self.heap[m].cmp(&self.heap[i]) == Ordering::Greater {
+            self.heap.swap(m, i);
+            if Self::is_grandchild(i, m) {
+                let parent = (m - 1) / 2;
+                if self.heap[m].cmp(&self.heap[parent]) == Ordering::Less {
+                    self.heap.swap(m, parent);
+                }
+                self.trickle_down_max(m);
+            }
+        }
+    }
+
+    fn smallest_descendant(&self, i: usize) -> Option<usize> {
+        Self::descendants(i, self.heap.len())
+            .into_iter()
+            .flatten()
+            .min_by(|&a, &b| self.heap[a].cmp(&self.heap[b]))
+    }
+
+    fn largest_descendant(&self, i: usize) -> Option<usize> {
+        Self::descendants(i, self.heap.len())
+            .into_iter()
+            .flatten()
+            .max_by(|&a, &b| self.heap[a].cmp(&self.heap[b]))
+    }
+
+    fn descendants(i: usize, len: usize) -> [Option<usize>; 6] {
+        let children = [2 * i + 1, 2 * i + 2];
+        let grandchildren = [4 * i + 3, 4 * i + 4, 4 * i + 5, 4 * i + 6];
+        let mut out = [None; 6];
+        for (slot, idx) in out.iter_mut().zip(children.into_iter().chain(grandchildren)) {
+            *slot = (idx < len).then_some(idx);
+        }
+        out
+    }
+
+    fn is_grandchild(ancestor: usize, descendant: usize) -> bool {
+        descendant > 2 * ancestor + 2
+    }
+}
+
+/// Extends the `Shortlist` with the contents of an iterator, exactly as if [`Shortlist::push`] had
+/// been called on each item in turn.
+///
+/// This lets a `Shortlist` be the target of `.extend(...)` in generic code, alongside the other
+/// standard collections. Only implemented for the default, `T: Ord`-ordered `Shortlist<T>`; a
+/// `Shortlist` with a custom comparator has no way to fabricate one out of thin air, so extend it
+/// by hand with [`Shortlist::append`] instead.
+impl<T: Ord> Extend<T> for Shortlist<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.append(iter);
+    }
+}
+
+/// Collects an iterator into a `Shortlist` with capacity equal to the number of items produced (or
+/// `1` if the iterator is empty), so nothing is ever evicted.
+///
+/// The standard [`FromIterator`] trait has no way to thread an explicit `capacity` through
+/// `.collect()`; if you want a genuine bounded top-`k` collection, use the inherent
+/// [`Shortlist::from_iter`] method instead, which takes a `capacity` directly.
+///
+/// Only implemented for the default, `T: Ord`-ordered `Shortlist<T>`, for the same reason as the
+/// [`Extend`] impl above: a custom comparator can't be conjured from the trait alone.
+///
+/// # Example
+/// ```
+/// use shortlist::Shortlist;
+///
+/// let shortlist: Shortlist<i32> = [3, 1, 4, 1, 5].into_iter().collect();
+/// assert_eq!(shortlist.into_sorted_vec(), vec![1, 1, 3, 4, 5]);
+/// ```
+impl<T: Ord> FromIterator<T> for Shortlist<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Shortlist<T> {
+        let items: Vec<T> = iter.into_iter().collect();
+        let mut shortlist = Shortlist::new(items.len().max(1));
+        shortlist.append(items);
+        shortlist
+    }
+}
+
+/// Serializes a `Shortlist` as its capacity plus the currently-retained items (in arbitrary heap
+/// order); the `Deserialize` impl below rebuilds a valid heap from this representation rather than
+/// trusting the item order.
+///
+/// Requires the `serde` feature.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Shortlist<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Shortlist", 2)?;
+        state.serialize_field("capacity", &self.capacity)?;
+        state.serialize_field("items", &self.heap)?;
+        state.end()
+    }
+}
+
+/// Deserializes a `Shortlist` from its capacity plus a list of items, re-establishing the heap
+/// invariant from scratch (the item order in the serialized data is not trusted) and rejecting
+/// inputs whose item count exceeds the stored capacity.
+///
+/// Since a deserialized `Shortlist` has no closure to restore for a custom comparator (see
+/// [`Shortlist::new_by`]), deserialization is only available for `T: Ord`, and always orders items
+/// by their own [`Ord`] implementation.
+///
+/// Requires the `serde` feature.
+#[cfg(feature = "serde")]
+impl<'de, T: Ord + serde::Deserialize<'de>> serde::Deserialize<'de> for Shortlist<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Raw<T> {
+            capacity: usize,
+            items: Vec<T>,
+        }
+
+        let raw = Raw::<T>::deserialize(deserializer)?;
+        if raw.capacity == 0 {
+            return Err(serde::de::Error::custom(
+                "Shortlist capacity must be greater than 0",
+            ));
+        }
+        if raw.items.len() > raw.capacity {
+            return Err(serde::de::Error::custom(format!(
+                "Shortlist data contains {} items, which exceeds its capacity of {}",
+                raw.items.len(),
+                raw.capacity
+            )));
+        }
+        let mut shortlist = Shortlist::new(raw.capacity);
+        shortlist.append(raw.items);
+        Ok(shortlist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shortlist;
+    use rand::prelude::*;
+
+    /* ===== HELPER FUNCTIONS ===== */
+
+    /// Given a sorted [`Vec`] of input values and a sorted [`Vec`] of the values taken from a
+    /// [`Shortlist`] of those items, checks that the [`Shortlist`] behaved correctly.
+    fn check_sorted_vecs<T: Ord + Eq + std::fmt::Debug>(
+        sorted_input_values: Vec<T>,
+        shortlist_vec: Vec<T>,
+        capacity: usize,
+    ) {
+        let mut debug_lines = Vec::with_capacity(1000);
+        debug_lines.push("".to_string());
+        debug_lines.push(format!("Input length      : {}", sorted_input_values.len()));
+        debug_lines.push(format!("Shortlist capacity: {}", capacity));
+        debug_lines.push(format!("Shortlist length  : {}", shortlist_vec.len()));
+        // let shortlist_vec = shortlist.into_sorted_vec();
+        // Check that the shortlist's length is the minimum of its capacity and the number of input
+        // values
+        if shortlist_vec.len() != capacity.min(sorted_input_values.len()) {
+            debug_lines.push(format!("Input values: {:?}", sorted_input_values));
+            debug_lines.push(format!("Shortlisted values: {:?}", shortlist_vec));
+            // Print the debug info before panicking
+            for line in debug_lines {
+                println!("{}", line);
+            }
+            panic!();
+        }
+        // Check that `shortlist.into_sorted_vec()` produces a suffix of `input_values` (we can
+        // guaruntee that the input values are sorted).
+        for (val, exp_val) in shortlist_vec
+            .iter()
+            .rev()
+            .zip(sorted_input_values.iter().rev())
+        {
+            if val == exp_val {
+                debug_lines.push(format!("{:?} == {:?}", val, exp_val));
+            } else {
+                debug_lines.push(format!("{:?} != {:?}", val, exp_val));
+                // Print the debug info before panicking
+                for line in debug_lines {
+                    println!("{}", line);
+                }
+                panic!();
+            }
+        }
+    }
+
+    /// Generates a random capacity and randomised input [`Vec`] to be used as a test sample.
+    fn gen_sample_input(rng: &mut impl Rng) -> (usize, Vec<usize>) {
+        // Decide how much capacity the shortlist will have
+        let capacity = rng.gen_range(1, 100);
+        // Make empty collections
+        let mut input_values: Vec<usize> = Vec::new();
+        // Populate both collections with the same values
+        for _ in 0..rng.gen_range(1, 1000) {
+            let val = rng.gen_range(0, 1000);
+            input_values.push(val);
+        }
+        (capacity, input_values)
+    }
+
+    /// Generates a randomised chunk of input data and a [`Shortlist`] built from that data.  The
+    /// [`Vec`] returned is always sorted, though the [`Shortlist`] is generated from the unsorted
+    /// data to be a fair test.
+    fn generate_input_and_shortlist(rng: &mut impl Rng) -> (Vec<usize>, Shortlist<usize>) {
+        let (capacity, mut input_values) = gen_sample_input(rng);
+        let shortlist: Shortlist<usize> = Shortlist::from_slice(capacity, &input_values);
+        // Sort the input values and return
+        input_values.sort();
+        (input_values, shortlist)
+    }
+
+    /// Test a given check over [`Shortlist`]s many many times.
+    fn check_correctness(check: impl Fn(Vec<usize>, Shortlist<usize>) -> ()) {
+        let mut rng = thread_rng();
+        // Make a shortlist with a known set of values
+        for _ in 1..10_000 {
+            let (input_values, shortlist) = generate_input_and_shortlist(&mut rng);
+            // Check that the shortlist contains a suffix of the sorted reference vec
+            check(input_values, shortlist);
+        }
+    }
+
+    /* ===== TESTING FUNCTIONS ===== */
+
+    #[test]
+    fn iter() {
+        check_correctness(|values, shortlist| {
+            // Store the capacity for both tests to use
+            let capacity = shortlist.capacity();
+            // Unload the Shortlist using `Shortlist::iter`
+            let mut shortlist_vec: Vec<usize> = shortlist.iter().copied().collect();
+            shortlist_vec.sort();
+            check_sorted_vecs(values, shortlist_vec, capacity);
+        });
+    }
+
+    #[test]
+    fn into_sorted_vec() {
+        check_correctness(|values, shortlist| {
+            let capacity = shortlist.capacity();
             let shortlist_vec = shortlist.into_sorted_vec();
             check_sorted_vecs(values, shortlist_vec, capacity);
         });
@@ -872,4 +2151,107 @@ mod tests {
             check_sorted_vecs(input_values, shortlist_vec, capacity);
         }
     }
+
+    #[test]
+    fn peek_max() {
+        check_correctness(|values, shortlist| {
+            // `values` is sorted ascending, so its last element (if any) is the largest
+            assert_eq!(shortlist.peek_max(), values.last());
+        });
+    }
+
+    #[test]
+    fn into_iter_sorted_is_descending() {
+        check_correctness(|values, shortlist| {
+            let capacity = shortlist.capacity();
+            // The top `capacity` values are the suffix of the sorted reference vec; descending
+            // order is that suffix reversed
+            let expected: Vec<usize> = values.iter().rev().take(capacity).copied().collect();
+            let shortlist_vec: Vec<usize> = shortlist.into_iter_sorted().collect();
+            assert_eq!(shortlist_vec, expected);
+        });
+    }
+
+    /// Tests [`Shortlist::new_by`] with a comparator that reverses the usual ordering, so the
+    /// shortlist keeps the *smallest* items instead of the largest.
+    #[test]
+    fn new_by_reversed_comparator() {
+        let mut rng = thread_rng();
+        for _ in 1..10_000 {
+            let (capacity, input_values) = gen_sample_input(&mut rng);
+            let mut shortlist = Shortlist::new_by(capacity, |a: &usize, b: &usize| b.cmp(a));
+            shortlist.append(input_values.iter().copied());
+            let mut expected = input_values.clone();
+            expected.sort();
+            expected.truncate(capacity.min(expected.len()));
+            let mut shortlist_vec = shortlist.into_vec();
+            shortlist_vec.sort();
+            assert_eq!(shortlist_vec, expected);
+        }
+    }
+
+    /// Tests [`Shortlist::new_by_key`] with a key that reverses the usual ordering, mirroring
+    /// [`new_by_reversed_comparator`] above but exercised through the key-based constructor.
+    #[test]
+    fn new_by_key_reversed() {
+        let mut rng = thread_rng();
+        for _ in 1..10_000 {
+            let (capacity, input_values) = gen_sample_input(&mut rng);
+            let mut shortlist = Shortlist::new_by_key(capacity, |x: &usize| std::cmp::Reverse(*x));
+            shortlist.append(input_values.iter().copied());
+            let mut expected = input_values.clone();
+            expected.sort();
+            expected.truncate(capacity.min(expected.len()));
+            let mut shortlist_vec = shortlist.into_vec();
+            shortlist_vec.sort();
+            assert_eq!(shortlist_vec, expected);
+        }
+    }
+
+    /// Tests [`SmallShortlist`] on both sides of its inline/spilled boundary (`N`).
+    #[test]
+    fn small_shortlist_across_inline_boundary() {
+        use super::SmallShortlist;
+
+        let mut rng = thread_rng();
+        for _ in 1..10_000 {
+            // `N` is fixed at 4 so every generated capacity lands on both sides of the boundary
+            // across iterations: some runs stay entirely inline, others spill to the heap.
+            let capacity = rng.gen_range(1, 10);
+            let mut input_values: Vec<usize> = Vec::new();
+            for _ in 0..rng.gen_range(1, 20) {
+                input_values.push(rng.gen_range(0, 1000));
+            }
+            let mut shortlist: SmallShortlist<usize, 4> = SmallShortlist::new(capacity);
+            shortlist.append_slice(&input_values);
+            let mut expected = input_values.clone();
+            expected.sort();
+            check_sorted_vecs(expected, shortlist.into_sorted_vec(), capacity);
+        }
+    }
+
+    /// Tests that a [`Shortlist`] survives a serde round-trip, and that deserializing data whose
+    /// item count exceeds its stated capacity is rejected.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_and_over_capacity_rejection() {
+        let mut rng = thread_rng();
+        for _ in 1..10_000 {
+            let (capacity, mut input_values) = gen_sample_input(&mut rng);
+            let shortlist: Shortlist<usize> = Shortlist::from_slice(capacity, &input_values);
+
+            let serialized = serde_json::to_string(&shortlist).unwrap();
+            let deserialized: Shortlist<usize> = serde_json::from_str(&serialized).unwrap();
+
+            input_values.sort();
+            let mut shortlist_vec = deserialized.into_sorted_vec();
+            shortlist_vec.sort();
+            check_sorted_vecs(input_values, shortlist_vec, capacity);
+        }
+
+        // An item count that exceeds the stated capacity must be rejected rather than silently
+        // truncated or allowed to overflow the heap invariant.
+        let over_capacity = serde_json::json!({ "capacity": 2, "items": [1, 2, 3] }).to_string();
+        assert!(serde_json::from_str::<Shortlist<usize>>(&over_capacity).is_err());
+    }
 }